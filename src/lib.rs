@@ -1,7 +1,7 @@
 #![doc = include_str!("../README.md")]
 use std::collections::hash_map::DefaultHasher;
 use std::hash::Hasher;
-use std::io::{Read, Write};
+use std::io::{BufRead, Read, Seek, SeekFrom, Write};
 
 /// Trait representing a computation performed on some bytes.
 ///
@@ -36,14 +36,23 @@ impl<H: Hasher> Check for H {
 /// It is possible for the checker to get out of sync with the actual bytes
 /// written if bytes are buffered and execution is interrupted before `.flush()` is called.
 /// The same is true if a failed read advances the underlying reader without returning the bytes read.
+///
+/// If `T: Seek`, a non-trivial [`seek`](Seek::seek) resets the [Check] (see the [Seek]
+/// impl), since a running checksum is only meaningful over a contiguous byte stream.
+/// [`is_contiguous`](Checker::is_contiguous) reports whether that has happened.
 pub struct Checker<C: Check, T> {
     checker: C,
     inner: T,
+    contiguous: bool,
 }
 
 impl<C: Check, T> Checker<C, T> {
     pub fn new(checker: C, inner: T) -> Self {
-        Self { checker, inner }
+        Self {
+            checker,
+            inner,
+            contiguous: true,
+        }
     }
 
     /// Insert a new [Check] struct, returning the old one.
@@ -67,9 +76,24 @@ impl<C: Check, T> Checker<C, T> {
     /// Destroy the struct and create a new one, re-using the existing [Check] struct.
     ///
     /// This allows the inner value to be replaced with one of a different type.
+    ///
+    /// Unlike [`new`](Checker::new), this preserves [`is_contiguous`](Checker::is_contiguous):
+    /// the [Check] carries over unchanged, so a checker already poisoned by a seek stays
+    /// poisoned in the rebuilt one.
     pub fn rebuild_with_inner<T2>(self, inner: T2) -> (Checker<C, T2>, T) {
-        let (h, inner1) = self.into_parts();
-        (Checker::new(h, inner), inner1)
+        let Checker {
+            checker,
+            inner: old_inner,
+            contiguous,
+        } = self;
+        (
+            Checker {
+                checker,
+                inner,
+                contiguous,
+            },
+            old_inner,
+        )
     }
 
     /// Destroy the struct, returning its component [Check] and inner structs as a tuple.
@@ -81,6 +105,23 @@ impl<C: Check, T> Checker<C, T> {
     pub fn output(&self) -> C::Output {
         self.checker.output()
     }
+
+    /// Whether `output()` still covers the entire byte stream.
+    ///
+    /// This is `false` after a [`seek`](Seek::seek) that repositions `inner`, since the
+    /// [Check] is reset at that point and no longer reflects bytes seen before the seek.
+    pub fn is_contiguous(&self) -> bool {
+        self.contiguous
+    }
+
+    /// Wrap this checker in a [Verifier] which checks its output against `expected`
+    /// once the stream is finalized.
+    pub fn verify(self, expected: C::Output) -> Verifier<C, T> {
+        Verifier {
+            checker: self,
+            expected,
+        }
+    }
 }
 
 impl<T> Checker<DefaultHasher, T> {
@@ -89,6 +130,7 @@ impl<T> Checker<DefaultHasher, T> {
         Self {
             checker: DefaultHasher::default(),
             inner,
+            contiguous: true,
         }
     }
 
@@ -100,7 +142,41 @@ impl<T> Checker<DefaultHasher, T> {
 
 impl<C: Default + Check, T> Checker<C, T> {
     pub fn new_default(inner: T) -> Self {
-        Self { checker: Default::default(), inner }
+        Self {
+            checker: Default::default(),
+            inner,
+            contiguous: true,
+        }
+    }
+}
+
+impl<C: Check + Default, T: Seek> Seek for Checker<C, T> {
+    /// Seek the inner value.
+    ///
+    /// A running [Check] is only meaningful over a contiguous byte stream, so any seek
+    /// other than a no-op `SeekFrom::Current(0)` query resets the [Check] via
+    /// `C::default()`: `output()` afterwards covers only bytes seen since the seek.
+    /// Use [`seek_stream_position`](Checker::seek_stream_position) to query the position
+    /// without resetting, and [`is_contiguous`](Checker::is_contiguous) to check whether
+    /// a resetting seek has happened.
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        if matches!(pos, SeekFrom::Current(0)) {
+            return self.inner.stream_position();
+        }
+        let result = self.inner.seek(pos)?;
+        self.checker = C::default();
+        self.contiguous = false;
+        Ok(result)
+    }
+}
+
+impl<C: Check, T: Seek> Checker<C, T> {
+    /// Query the current stream position without resetting the [Check].
+    ///
+    /// Equivalent to `seek(SeekFrom::Current(0))`, but available without the `C:
+    /// Default` bound that [`Seek::seek`] needs for its resetting seeks.
+    pub fn seek_stream_position(&mut self) -> std::io::Result<u64> {
+        self.inner.stream_position()
     }
 }
 
@@ -124,6 +200,26 @@ impl<C: Check, W: Write> Write for Checker<C, W> {
     }
 }
 
+impl<C: Check, R: BufRead> BufRead for Checker<C, R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        // Bytes returned here may only be peeked, not consumed, so the checker
+        // must not see them until `consume` is called.
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let buf = self
+            .inner
+            .fill_buf()
+            .expect("fill_buf must succeed after a previous successful fill_buf");
+        // `BufRead::consume`'s contract allows callers to pass an `amt` larger than what
+        // is actually buffered; clamp rather than panic, matching `BufReader::consume`.
+        let amt = amt.min(buf.len());
+        self.checker.update(&buf[..amt]);
+        self.inner.consume(amt);
+    }
+}
+
 /// Type implementing [Check] used by [Counter] for counting bytes as they pass through.
 #[derive(Debug, Default)]
 pub struct InnerCounter(usize);
@@ -143,3 +239,571 @@ impl Check for InnerCounter {
 /// Type which counts the number of bytes passed through.
 /// Useful for wrapping readers/writers before (or after) they are wrapped in compressors.
 pub type Counter<T> = Checker<InnerCounter, T>;
+
+/// Output of [GzipCrc]: a running CRC32 and input length, as stored in a gzip member trailer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GzipCrcOutput {
+    /// CRC32 of all bytes seen so far.
+    pub crc32: u32,
+    /// Total number of bytes seen so far, modulo 2^32.
+    pub isize: u32,
+}
+
+impl GzipCrcOutput {
+    /// Serialize as the 8-byte gzip member trailer: little-endian CRC32 followed by little-endian ISIZE.
+    pub fn to_trailer(&self) -> [u8; 8] {
+        let mut out = [0u8; 8];
+        out[..4].copy_from_slice(&self.crc32.to_le_bytes());
+        out[4..].copy_from_slice(&self.isize.to_le_bytes());
+        out
+    }
+}
+
+/// [Check] implementation tracking a CRC32 and total byte count, as used in a gzip member trailer.
+#[derive(Debug, Default)]
+pub struct GzipCrc {
+    hasher: crc32fast::Hasher,
+    isize: u32,
+}
+
+impl Check for GzipCrc {
+    type Output = GzipCrcOutput;
+
+    fn update(&mut self, buf: &[u8]) {
+        self.hasher.update(buf);
+        self.isize = self.isize.wrapping_add(buf.len() as u32);
+    }
+
+    fn output(&self) -> Self::Output {
+        GzipCrcOutput {
+            crc32: self.hasher.clone().finalize(),
+            isize: self.isize,
+        }
+    }
+}
+
+/// [Check] combinator running two checks over the same bytes in a single pass.
+///
+/// Both `A` and `B` see every call to `update`, and [`output`](Check::output) returns
+/// both results as a tuple. Nest `And<And<A, B>, C>` to compose three or more checks.
+///
+/// There's no inherent blanket impl over plain `(A, B)` tuples: it would conflict with
+/// the existing `impl<H: Hasher> Check for H` (the coherence checker can't rule out a
+/// future upstream `Hasher` impl for tuples), so `And` exists as its own newtype instead.
+#[derive(Debug, Default)]
+pub struct And<A: Check, B: Check>(pub A, pub B);
+
+impl<A: Check, B: Check> Check for And<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn update(&mut self, buf: &[u8]) {
+        self.0.update(buf);
+        self.1.update(buf);
+    }
+
+    fn output(&self) -> Self::Output {
+        (self.0.output(), self.1.output())
+    }
+}
+
+/// Wraps a [Checker] with an expected [`Check::Output`], turning "compute a checksum"
+/// into "enforce a checksum".
+///
+/// For readers, call [`finish`](Verifier::finish) once the caller has read to EOF:
+/// it compares the computed output against the expected one and fails with
+/// `io::ErrorKind::InvalidData` on mismatch. For writers, the same comparison runs
+/// inside `flush()`, so a corrupt or truncated write surfaces as an I/O error.
+pub struct Verifier<C: Check, T> {
+    checker: Checker<C, T>,
+    expected: C::Output,
+}
+
+impl<C: Check, T> Verifier<C, T> {
+    pub fn new(checker: C, inner: T, expected: C::Output) -> Self {
+        Self {
+            checker: Checker::new(checker, inner),
+            expected,
+        }
+    }
+}
+
+impl<C: Check, T> Verifier<C, T>
+where
+    C::Output: PartialEq,
+{
+    /// Compare the checker's current output against the expected one.
+    fn verify(&self) -> std::io::Result<()> {
+        if self.checker.output() == self.expected {
+            Ok(())
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "checksum mismatch",
+            ))
+        }
+    }
+
+    /// Destroy the struct, returning the wrapped inner value if the computed output
+    /// matches the expected one.
+    pub fn finish(self) -> std::io::Result<T> {
+        self.verify()?;
+        Ok(self.checker.into_parts().1)
+    }
+}
+
+impl<C: Check, R: Read> Read for Verifier<C, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.checker.read(buf)
+    }
+}
+
+impl<C: Check, W: Write> Write for Verifier<C, W>
+where
+    C::Output: PartialEq,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.checker.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.checker.flush()?;
+        self.verify()
+    }
+}
+
+/// A transformation applied to bytes in flight, such as a stream cipher or byte
+/// remapper, run alongside a [Check] by [TransformPipe].
+///
+/// Unlike [Check], a `Transform` may change the bytes, so `output` may be shorter than
+/// `input`. Implementations must not write more than `output.len()` bytes, and — since
+/// both [Internal] and [External] buffering size `output` to `input.len()` — must not
+/// need more than `input.len()` bytes of output space; growing transforms are not
+/// supported by either strategy today and will have their excess output silently
+/// dropped.
+pub trait Transform {
+    /// Transform `input` into `output`, returning the number of bytes written to `output`.
+    fn transform(&mut self, input: &[u8], output: &mut [u8]) -> usize;
+}
+
+/// Buffering strategy used by [TransformPipe] to hold bytes while they pass through a
+/// [Transform]. See [Internal] and [External] for the two strategies.
+pub trait Buffering: Default {
+    /// Transform `input` in [EXTERNAL_CHUNK]-sized pieces, feed each piece into `check`
+    /// only once `sink` confirms it was written, and return how many bytes of `input`
+    /// were fully transformed-and-written-through (used by the [Write] side of
+    /// [TransformPipe]).
+    ///
+    /// `sink` is a raw, single-shot `write` (not `write_all`): it returns how many bytes
+    /// it actually accepted, per [`Write::write`]'s contract, so implementations can
+    /// retry the remainder themselves instead of losing that count inside a `write_all`.
+    /// Following that same contract: if `sink` fails after at least one earlier byte/chunk
+    /// succeeded, that earlier progress is reported via `Ok(n)` rather than discarded into
+    /// an `Err` (the unwritten remainder is simply not counted, and will be retried on the
+    /// next call); if nothing has been confirmed written yet, the error is returned
+    /// directly.
+    fn write_through<X: Transform, C: Check>(
+        &mut self,
+        transform: &mut X,
+        check: &mut C,
+        input: &[u8],
+        sink: &mut dyn FnMut(&[u8]) -> std::io::Result<usize>,
+    ) -> std::io::Result<usize>;
+
+    /// Read raw bytes from `reader`, transform them into `output`, feed the transformed
+    /// bytes into `check`, and return how many were written to `output` (used by the
+    /// [Read] side of [TransformPipe]).
+    fn read_through<X: Transform, C: Check, R: Read>(
+        &mut self,
+        transform: &mut X,
+        check: &mut C,
+        reader: &mut R,
+        output: &mut [u8],
+    ) -> std::io::Result<usize>;
+}
+
+/// Chunk size, in bytes, that [External] buffering transforms at a time, and that
+/// [Internal] buffering transforms at a time on write.
+///
+/// Bounding the chunk size also bounds how much of a failed [`write_through`](Buffering::write_through)
+/// call's progress can go unreported: see [Internal]'s docs.
+const EXTERNAL_CHUNK: usize = 4096;
+
+/// [Buffering] strategy that keeps a scratch [Vec<u8>] owned by the pipe.
+///
+/// On write, input is transformed in [EXTERNAL_CHUNK]-sized pieces; on read, up to the
+/// caller's buffer length is read and transformed at once. Bytes pass through the owned
+/// scratch buffers, which are reused across calls and only grow when a larger piece
+/// arrives, so this supports [Transform]s that shrink the bytes (or leave them the same
+/// length) without needing [External]'s length-preserving restriction. As with [Transform], it does *not* support transforms that grow their
+/// input — see that trait's docs.
+///
+/// # Limitation: partial writes mid-chunk
+///
+/// Because a shrinking [Transform] need not map its output back to the input byte-for-byte,
+/// `write_through` cannot tell how many *original* bytes a partially-written *transformed*
+/// chunk corresponds to. It therefore only counts a whole `EXTERNAL_CHUNK`-sized input chunk
+/// as written once every transformed byte for that chunk is confirmed written; if the inner
+/// writer accepts only a prefix of a chunk's transformed bytes before failing, those bytes
+/// are already in the inner writer but the chunk is not counted, so retrying it will
+/// re-transform and resend the whole chunk, duplicating that prefix. Use [External] instead
+/// if the inner writer may fail partway through a write and exact accounting matters.
+#[derive(Debug, Default)]
+pub struct Internal {
+    raw: Vec<u8>,
+    transformed: Vec<u8>,
+}
+
+impl Buffering for Internal {
+    fn write_through<X: Transform, C: Check>(
+        &mut self,
+        transform: &mut X,
+        check: &mut C,
+        input: &[u8],
+        sink: &mut dyn FnMut(&[u8]) -> std::io::Result<usize>,
+    ) -> std::io::Result<usize> {
+        let mut written = 0;
+        for chunk in input.chunks(EXTERNAL_CHUNK) {
+            self.transformed.resize(chunk.len(), 0);
+            let n = transform.transform(chunk, &mut self.transformed);
+            // Retry until the whole transformed chunk is confirmed written, same as
+            // `write_all`, but see the struct docs for why a partial acceptance here still
+            // can't be translated back into a partial *original*-byte count.
+            let mut offset = 0;
+            let chunk_result = loop {
+                if offset == n {
+                    break Ok(());
+                }
+                match sink(&self.transformed[offset..n]) {
+                    Ok(0) => break Err(std::io::Error::from(std::io::ErrorKind::WriteZero)),
+                    Ok(m) => offset += m,
+                    Err(e) => break Err(e),
+                }
+            };
+            match chunk_result {
+                Ok(()) => {
+                    // Only record the bytes in `check` once `sink` confirms they actually
+                    // made it out, mirroring `Checker::write`'s "check only reflects
+                    // confirmed I/O".
+                    check.update(&self.transformed[..n]);
+                    written += chunk.len();
+                }
+                Err(_) if written > 0 => return Ok(written),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(written)
+    }
+
+    fn read_through<X: Transform, C: Check, R: Read>(
+        &mut self,
+        transform: &mut X,
+        check: &mut C,
+        reader: &mut R,
+        output: &mut [u8],
+    ) -> std::io::Result<usize> {
+        self.raw.resize(output.len(), 0);
+        let raw_n = reader.read(&mut self.raw)?;
+        self.transformed.resize(raw_n, 0);
+        let n = transform.transform(&self.raw[..raw_n], &mut self.transformed);
+        output[..n].copy_from_slice(&self.transformed[..n]);
+        check.update(&output[..n]);
+        Ok(n)
+    }
+}
+
+/// [Buffering] strategy that avoids a heap allocation by transforming through a small
+/// stack buffer, looping over the input in chunks of up to [EXTERNAL_CHUNK] bytes.
+///
+/// Only suitable for transforms that are length-preserving (`output.len() == input.len()`
+/// for every chunk), since transformed bytes are written straight into the caller's
+/// buffer as they are produced. That same length-preservation means every transformed
+/// byte corresponds 1:1 to an original input byte at the same offset, so unlike
+/// [Internal], a `sink` failure partway through a chunk can still be reported exactly:
+/// `write_through` counts precisely the original bytes whose transformed counterpart was
+/// confirmed written, down to sub-chunk granularity, so nothing is silently duplicated.
+#[derive(Debug, Default)]
+pub struct External;
+
+impl Buffering for External {
+    fn write_through<X: Transform, C: Check>(
+        &mut self,
+        transform: &mut X,
+        check: &mut C,
+        input: &[u8],
+        sink: &mut dyn FnMut(&[u8]) -> std::io::Result<usize>,
+    ) -> std::io::Result<usize> {
+        let mut scratch = [0u8; EXTERNAL_CHUNK];
+        let mut written = 0;
+        for chunk in input.chunks(EXTERNAL_CHUNK) {
+            let n = transform.transform(chunk, &mut scratch[..chunk.len()]);
+            let mut offset = 0;
+            while offset < n {
+                match sink(&scratch[offset..n]) {
+                    Ok(0) if written > 0 => return Ok(written),
+                    Ok(0) => return Err(std::io::Error::from(std::io::ErrorKind::WriteZero)),
+                    Ok(m) => {
+                        // `scratch[offset..offset + m]` and the input bytes that produced
+                        // it share the same offset and length, so the byte count confirmed
+                        // written here is exactly how many original bytes are now written.
+                        check.update(&scratch[offset..offset + m]);
+                        offset += m;
+                        written += m;
+                    }
+                    Err(_) if written > 0 => return Ok(written),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(written)
+    }
+
+    fn read_through<X: Transform, C: Check, R: Read>(
+        &mut self,
+        transform: &mut X,
+        check: &mut C,
+        reader: &mut R,
+        output: &mut [u8],
+    ) -> std::io::Result<usize> {
+        let len = output.len().min(EXTERNAL_CHUNK);
+        let mut raw = [0u8; EXTERNAL_CHUNK];
+        let raw_n = reader.read(&mut raw[..len])?;
+        let n = transform.transform(&raw[..raw_n], &mut output[..raw_n]);
+        check.update(&output[..n]);
+        Ok(n)
+    }
+}
+
+/// Pipe that runs a [Transform] over bytes in flight while still running a [Check],
+/// mirroring how a stream-cipher `Sink`/`Source` wraps a writer/reader.
+///
+/// Because transformed output can differ in length from the input, and transformed
+/// bytes cannot always be written into the caller's read buffer in place, buffering is
+/// selected via `B`: [Internal] keeps an owned scratch [Vec<u8>], while [External]
+/// writes directly into the caller's buffer for length-preserving transforms.
+///
+/// As with [Checker], callers must `flush` to drain any partial internal buffer, or the
+/// [Check] (and, for [Write], the inner writer) can end up out of sync with the bytes
+/// actually seen.
+///
+/// `Write::write` is implemented in [EXTERNAL_CHUNK]-sized pieces, handed to the inner
+/// writer via raw `write` calls (not `write_all`) so that a failing inner writer doesn't
+/// lose already-written progress into an `Err` (see [`Buffering::write_through`]). With
+/// [External] this accounting is exact down to the byte; with [Internal] it can only be
+/// exact down to the chunk — see that struct's docs for why.
+pub struct TransformPipe<X: Transform, C: Check, T, B: Buffering = Internal> {
+    transform: X,
+    checker: C,
+    inner: T,
+    buffering: B,
+}
+
+impl<X: Transform, C: Check, T, B: Buffering> TransformPipe<X, C, T, B> {
+    pub fn new(transform: X, checker: C, inner: T) -> Self {
+        Self {
+            transform,
+            checker,
+            inner,
+            buffering: B::default(),
+        }
+    }
+
+    /// Return the current output value for all bytes seen by the [Check].
+    pub fn output(&self) -> C::Output {
+        self.checker.output()
+    }
+
+    /// Destroy the struct, returning its component [Transform], [Check], and inner value.
+    pub fn into_parts(self) -> (X, C, T) {
+        (self.transform, self.checker, self.inner)
+    }
+}
+
+impl<X: Transform, C: Check, W: Write, B: Buffering> Write for TransformPipe<X, C, W, B> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let inner = &mut self.inner;
+        self.buffering.write_through(
+            &mut self.transform,
+            &mut self.checker,
+            buf,
+            &mut |chunk| inner.write(chunk),
+        )
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<X: Transform, C: Check, R: Read, B: Buffering> Read for TransformPipe<X, C, R, B> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.buffering
+            .read_through(&mut self.transform, &mut self.checker, &mut self.inner, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufReader, Cursor};
+
+    fn counting_buf_reader(data: &[u8]) -> Counter<BufReader<Cursor<Vec<u8>>>> {
+        Checker::new_default(BufReader::new(Cursor::new(data.to_vec())))
+    }
+
+    #[test]
+    fn fill_buf_does_not_update_checker() {
+        let mut checker = counting_buf_reader(b"hello world");
+        checker.fill_buf().unwrap();
+        checker.fill_buf().unwrap();
+        assert_eq!(checker.output(), 0);
+    }
+
+    #[test]
+    fn consume_updates_checker_by_exactly_the_consumed_amount() {
+        let mut checker = counting_buf_reader(b"hello world");
+        checker.fill_buf().unwrap();
+        checker.consume(5);
+        assert_eq!(checker.output(), 5);
+        checker.fill_buf().unwrap();
+        checker.consume(1);
+        assert_eq!(checker.output(), 6);
+    }
+
+    #[test]
+    fn consume_clamps_an_over_large_amt_instead_of_panicking() {
+        let mut checker = counting_buf_reader(b"hi");
+        let buffered = checker.fill_buf().unwrap().len();
+        checker.consume(buffered + 1000);
+        assert_eq!(checker.output(), buffered);
+    }
+
+    #[test]
+    fn seek_current_zero_preserves_check_and_contiguity() {
+        let mut checker: Counter<Cursor<Vec<u8>>> =
+            Checker::new_default(Cursor::new(b"hello world".to_vec()));
+        checker.read_exact(&mut [0u8; 5]).unwrap();
+        checker.seek(SeekFrom::Current(0)).unwrap();
+        checker.seek_stream_position().unwrap();
+        assert_eq!(checker.output(), 5);
+        assert!(checker.is_contiguous());
+    }
+
+    #[test]
+    fn repositioning_seek_resets_check_and_breaks_contiguity() {
+        let mut checker: Counter<Cursor<Vec<u8>>> =
+            Checker::new_default(Cursor::new(b"hello world".to_vec()));
+        checker.read_exact(&mut [0u8; 5]).unwrap();
+        checker.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(checker.output(), 0);
+        assert!(!checker.is_contiguous());
+    }
+
+    /// Non-identity [Transform] that XORs every byte with a fixed key, used to exercise
+    /// [TransformPipe] with something other than a pass-through transform.
+    #[derive(Default)]
+    struct XorTransform;
+
+    impl Transform for XorTransform {
+        fn transform(&mut self, input: &[u8], output: &mut [u8]) -> usize {
+            for (o, i) in output.iter_mut().zip(input) {
+                *o = i ^ 0xff;
+            }
+            input.len()
+        }
+    }
+
+    fn assert_transform_pipe_roundtrip<B: Buffering>() {
+        let plaintext = b"the quick brown fox jumps over the lazy dog";
+
+        let mut write_pipe: TransformPipe<XorTransform, InnerCounter, Vec<u8>, B> =
+            TransformPipe::new(XorTransform, InnerCounter::default(), Vec::new());
+        write_pipe.write_all(plaintext).unwrap();
+        let ciphertext = write_pipe.output();
+        assert_eq!(ciphertext, plaintext.len());
+        let (_, _, ciphertext) = write_pipe.into_parts();
+        let expected: Vec<u8> = plaintext.iter().map(|b| b ^ 0xff).collect();
+        assert_eq!(ciphertext, expected);
+
+        let mut read_pipe: TransformPipe<XorTransform, InnerCounter, &[u8], B> =
+            TransformPipe::new(XorTransform, InnerCounter::default(), ciphertext.as_slice());
+        let mut roundtripped = Vec::new();
+        read_pipe.read_to_end(&mut roundtripped).unwrap();
+        assert_eq!(roundtripped, plaintext);
+        assert_eq!(read_pipe.output(), plaintext.len());
+    }
+
+    #[test]
+    fn transform_pipe_roundtrips_through_internal_buffering() {
+        assert_transform_pipe_roundtrip::<Internal>();
+    }
+
+    #[test]
+    fn transform_pipe_roundtrips_through_external_buffering() {
+        assert_transform_pipe_roundtrip::<External>();
+    }
+
+    /// Inner writer that accepts up to `budget` bytes (one [Write::write] call at a time,
+    /// never more than it's given credit for) and then fails every call after, used to
+    /// simulate an inner writer that fails partway through a [TransformPipe] write.
+    struct FailAfter {
+        budget: usize,
+        written: Vec<u8>,
+    }
+
+    impl Write for FailAfter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.budget == 0 {
+                return Err(std::io::Error::other("budget exhausted"));
+            }
+            let n = buf.len().min(self.budget);
+            self.written.extend_from_slice(&buf[..n]);
+            self.budget -= n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn external_write_reports_exactly_the_bytes_confirmed_written_on_partial_failure() {
+        let plaintext = b"the quick brown fox jumps over the lazy dog";
+        let mut pipe: TransformPipe<XorTransform, InnerCounter, FailAfter, External> =
+            TransformPipe::new(
+                XorTransform,
+                InnerCounter::default(),
+                FailAfter {
+                    budget: 10,
+                    written: Vec::new(),
+                },
+            );
+
+        let n = pipe.write(plaintext).unwrap();
+        assert_eq!(n, 10);
+        assert_eq!(pipe.output(), 10);
+        let (_, _, inner) = pipe.into_parts();
+        let expected: Vec<u8> = plaintext[..10].iter().map(|b| b ^ 0xff).collect();
+        assert_eq!(inner.written, expected);
+    }
+
+    #[test]
+    fn internal_write_only_counts_whole_chunks_on_partial_failure() {
+        // Smaller than `EXTERNAL_CHUNK`, so the whole input is a single chunk: the inner
+        // writer accepts only a 10-byte prefix of it before failing, so the chunk is not
+        // counted and nothing should reach `check`.
+        let plaintext = b"the quick brown fox jumps over the lazy dog";
+        let mut pipe: TransformPipe<XorTransform, InnerCounter, FailAfter, Internal> =
+            TransformPipe::new(
+                XorTransform,
+                InnerCounter::default(),
+                FailAfter {
+                    budget: 10,
+                    written: Vec::new(),
+                },
+            );
+
+        assert!(pipe.write(plaintext).is_err());
+        assert_eq!(pipe.output(), 0);
+    }
+}